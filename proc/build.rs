@@ -6,4 +6,8 @@ fn main() {
     let lock_path = lock_path.to_str().expect("Lock path is not UTF-8").to_string();
 
     println!("cargo::rustc-env=VTID_PROC_MACRO_LOCK_FILE_PATH={}", lock_path);
+
+    // `VTID_BASE_ID` is read at macro-expansion time, not baked in here, but this at least
+    // makes this crate itself rebuild when it changes.
+    println!("cargo::rerun-if-env-changed=VTID_BASE_ID");
 }
@@ -11,6 +11,20 @@
 //!
 //! The versioning ensures that if a type's definition changes and the crate is recompiled,
 //! the old and new versions of the type will have different IDs.
+//!
+//! # Reproducible builds
+//!
+//! The counter above bumps on every invocation, including a rebuild with zero source changes,
+//! which breaks reproducible builds and CI caching. Setting the `VTID_BASE_ID` environment
+//! variable opts out of it:
+//!
+//! - `VTID_BASE_ID=<u64>` pins the base id to that exact value.
+//! - `VTID_BASE_ID=content` derives the base id from a hash of `CARGO_PKG_VERSION` and the
+//!   `rustc` version, so it stays the same across machines as long as both match.
+//!
+//! Because this is read at macro-expansion time rather than baked in through `build.rs`,
+//! cargo does not know to re-expand the derive when `VTID_BASE_ID` changes without a source
+//! change; set it before a clean build, or touch the derived type, when you change it.
 
 extern crate proc_macro;
 
@@ -85,8 +99,109 @@ fn get_next_counter() -> u64 {
     read_and_update_counter(&mut *_guard.file).expect("Failed to read and update counter")
 }
 
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a_hash(bytes: &[u8], hash: &mut u64) {
+    for &byte in bytes {
+        *hash ^= byte as u64;
+        *hash = hash.wrapping_mul(FNV_PRIME);
+    }
+}
+
+fn hash_member(member: &syn::Member, hash: &mut u64) {
+    match member {
+        syn::Member::Named(ident) => fnv1a_hash(ident.to_string().as_bytes(), hash),
+        syn::Member::Unnamed(index) => fnv1a_hash(index.index.to_string().as_bytes(), hash),
+    }
+}
+
+fn hash_fields(fields: &syn::Fields, hash: &mut u64) {
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(index)),
+        };
+        hash_member(&member, hash);
+        let ty = &field.ty;
+        fnv1a_hash(quote::quote!(#ty).to_string().as_bytes(), hash);
+    }
+}
+
+/// Returns the `rustc` version string, by shelling out to the compiler cargo points at.
+fn rustc_version_string() -> String {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+
+    std::process::Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default()
+}
+
+/// Derives a base id from the crate version and the `rustc` version instead of a mutable
+/// counter, so it is reproducible across machines that share both.
+fn content_base_id() -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    fnv1a_hash(
+        env::var("CARGO_PKG_VERSION").unwrap_or_default().as_bytes(),
+        &mut hash,
+    );
+    fnv1a_hash(rustc_version_string().as_bytes(), &mut hash);
+    hash
+}
+
+/// Resolves the crate-wide base id: an explicit `VTID_BASE_ID` environment variable pins or
+/// derives it, otherwise it falls back to the auto-incrementing lock-file counter.
+fn get_base_id() -> u64 {
+    match env::var("VTID_BASE_ID") {
+        Ok(value) if value == "content" => content_base_id(),
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("VTID_BASE_ID must be a u64 or \"content\", got {value:?}")),
+        Err(_) => get_next_counter(),
+    }
+}
+
 lazy_static::lazy_static! {
-    static ref BASE_ID: u64 = get_next_counter();
+    static ref BASE_ID: u64 = get_base_id();
+}
+
+/// Computes a stable structural fingerprint of a type's shape.
+///
+/// The hash only depends on field members, field types and, for enums, variant names and
+/// discriminants - all in declaration order. It does not depend on doc comments, attributes
+/// or whitespace, so reordering a derive invocation across recompiles does not change it,
+/// but adding/removing/reordering/retyping a field or variant does.
+fn structural_hash(input: &syn::DeriveInput) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+
+    match &input.data {
+        syn::Data::Struct(data) => {
+            fnv1a_hash(b"struct", &mut hash);
+            hash_fields(&data.fields, &mut hash);
+        }
+        syn::Data::Enum(data) => {
+            fnv1a_hash(b"enum", &mut hash);
+            for variant in &data.variants {
+                fnv1a_hash(variant.ident.to_string().as_bytes(), &mut hash);
+                hash_fields(&variant.fields, &mut hash);
+                if let Some((_, discriminant)) = &variant.discriminant {
+                    fnv1a_hash(
+                        quote::quote!(#discriminant).to_string().as_bytes(),
+                        &mut hash,
+                    );
+                }
+            }
+        }
+        syn::Data::Union(data) => {
+            fnv1a_hash(b"union", &mut hash);
+            hash_fields(&syn::Fields::Named(data.fields.clone()), &mut hash);
+        }
+    }
+
+    hash
 }
 
 #[proc_macro_derive(HasVtid)]
@@ -94,6 +209,7 @@ pub fn derive_answer_fn(item: TokenStream) -> TokenStream {
     let mut input = syn::parse_macro_input!(item as syn::DeriveInput);
 
     let ident = &input.ident;
+    let type_hash = structural_hash(&input);
 
     let where_clause = input.generics.make_where_clause();
     where_clause.predicates.push(syn::parse_quote!(Self: 'static));
@@ -109,7 +225,11 @@ pub fn derive_answer_fn(item: TokenStream) -> TokenStream {
             Some(where_clause)
         };
 
-    let base_id = *BASE_ID;
+    // Fold the per-type structural hash into the crate-wide base id, so a type's id also
+    // depends on its own definition. With a pinned `VTID_BASE_ID`, a type whose definition is
+    // unchanged keeps its id across recompiles; with the default lock-file counter, the base id
+    // changes on every compilation, so every type's id changes, unchanged ones included.
+    let base_id = *BASE_ID ^ type_hash;
 
     let tokens = quote::quote! {
         impl #impl_generics ::vtid::HasVtid for #ident #ty_generics #where_clause {
@@ -0,0 +1,182 @@
+use core::any::{Any, TypeId};
+
+use crate::{HasVtid, Vtid};
+
+/// An `Any`-like trait whose downcasts are additionally guarded by [`Vtid`].
+///
+/// `TypeId` alone cannot distinguish two layouts of "the same" type across a crate recompile,
+/// which is exactly the failure mode `Vtid` exists to catch. `VtidAny` closes that gap for
+/// trait objects: downcasting requires both the stored `Vtid` to match the current
+/// `Vtid::of::<T>()` *and* the value's real `core::any::TypeId` to match `TypeId::of::<T>()`,
+/// so a value produced by a stale compilation of a cdylib is rejected instead of being
+/// reinterpreted through an unchecked pointer cast.
+///
+/// The `TypeId` check is the one doing the safety-critical work - it is what the unsafe cast
+/// behind `downcast_ref`/`downcast_mut`/`downcast` actually relies on. The `Vtid` check only
+/// narrows things further: it catches a recompiled-but-`TypeId`-coincidentally-unchanged type,
+/// the scenario `Vtid` exists for in the first place.
+///
+/// There is a blanket implementation for every `T: HasVtid`, so types that derive `HasVtid`
+/// automatically support `dyn VtidAny`.
+pub trait VtidAny: Any {
+    #[doc(hidden)]
+    fn vtid(&self) -> Vtid;
+}
+
+impl<T: HasVtid + Any> VtidAny for T {
+    fn vtid(&self) -> Vtid {
+        Vtid::of::<T>()
+    }
+}
+
+impl dyn VtidAny {
+    /// Returns a reference to the contained value if it is of type `T` *and* its `Vtid`
+    /// matches the current `Vtid::of::<T>()`.
+    pub fn downcast_ref<T: HasVtid + Any>(&self) -> Option<&T> {
+        if self.vtid() != Vtid::of::<T>() || self.type_id() != TypeId::of::<T>() {
+            return None;
+        }
+
+        // SAFETY: `self.type_id() == TypeId::of::<T>()` was just checked above, so this
+        // value really is a `T`. The `Vtid` check is not load-bearing for this cast.
+        Some(unsafe { &*(self as *const dyn VtidAny as *const T) })
+    }
+
+    /// Returns a mutable reference to the contained value if it is of type `T` *and* its
+    /// `Vtid` matches the current `Vtid::of::<T>()`.
+    pub fn downcast_mut<T: HasVtid + Any>(&mut self) -> Option<&mut T> {
+        // Reborrowed as shared first: `(&mut dyn VtidAny).type_id()` would otherwise tie the
+        // returned `TypeId` to the lifetime of the `&mut self` borrow (E0521), since the
+        // unsized trait object behind `&mut self` isn't inferred `'static` the way it is
+        // behind `&self`.
+        let shared: &Self = self;
+        if shared.vtid() != Vtid::of::<T>() || shared.type_id() != TypeId::of::<T>() {
+            return None;
+        }
+
+        // SAFETY: see `downcast_ref`.
+        Some(unsafe { &mut *(self as *mut dyn VtidAny as *mut T) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl dyn VtidAny {
+    /// Attempts to downcast the box to a concrete type, guarded by `Vtid` and `TypeId` equality.
+    ///
+    /// Returns the original box back in `Err` when either check fails.
+    pub fn downcast<T: HasVtid + Any>(
+        self: alloc::boxed::Box<Self>,
+    ) -> Result<alloc::boxed::Box<T>, alloc::boxed::Box<Self>> {
+        // `(*self).type_id()`, not `self.type_id()`: `Box<dyn VtidAny>` is itself `'static` +
+        // `Sized`, so an un-dereferenced `self.type_id()` would resolve to the blanket
+        // `impl Any for Box<dyn VtidAny>` and report the box's own TypeId, not the boxed
+        // value's, before autoderef ever reaches `dyn VtidAny`'s vtable.
+        if (*self).vtid() != Vtid::of::<T>() || (*self).type_id() != TypeId::of::<T>() {
+            return Err(self);
+        }
+
+        // SAFETY: see `downcast_ref`.
+        Ok(unsafe { alloc::boxed::Box::from_raw(alloc::boxed::Box::into_raw(self) as *mut T) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::any::TypeId;
+
+    use super::*;
+    use crate::hash_type_id;
+
+    struct Foo(u32);
+    impl HasVtid for Foo {
+        fn vtid() -> Vtid {
+            Vtid {
+                base_id: 1,
+                tid_hash: hash_type_id(TypeId::of::<Foo>()),
+            }
+        }
+    }
+
+    struct Bar(u64);
+    impl HasVtid for Bar {
+        fn vtid() -> Vtid {
+            Vtid {
+                base_id: 1,
+                tid_hash: hash_type_id(TypeId::of::<Bar>()),
+            }
+        }
+    }
+
+    struct StaleFoo(u32);
+    impl HasVtid for StaleFoo {
+        fn vtid() -> Vtid {
+            // A different base_id, as if this were Foo's own Vtid from an older compilation.
+            Vtid {
+                base_id: 2,
+                tid_hash: hash_type_id(TypeId::of::<Foo>()),
+            }
+        }
+    }
+
+    /// A distinct concrete type whose `Vtid` is made to collide with `Foo`'s, simulating a
+    /// 64-bit hash collision between two unrelated types.
+    struct Evil(u64);
+    impl HasVtid for Evil {
+        fn vtid() -> Vtid {
+            <Foo as HasVtid>::vtid()
+        }
+    }
+
+    #[test]
+    fn downcast_ref_accepts_matching_type() {
+        let foo = Foo(42);
+        let any_ref: &dyn VtidAny = &foo;
+        assert_eq!(any_ref.downcast_ref::<Foo>().map(|foo| foo.0), Some(42));
+    }
+
+    #[test]
+    fn downcast_mut_accepts_matching_type() {
+        let mut foo = Foo(1);
+        let any_mut: &mut dyn VtidAny = &mut foo;
+        any_mut.downcast_mut::<Foo>().unwrap().0 = 7;
+        assert_eq!(foo.0, 7);
+    }
+
+    #[test]
+    fn downcast_ref_rejects_different_type() {
+        let foo = Foo(1);
+        let any_ref: &dyn VtidAny = &foo;
+        assert!(any_ref.downcast_ref::<Bar>().is_none());
+    }
+
+    #[test]
+    fn downcast_ref_rejects_stale_vtid() {
+        let stale = StaleFoo(1);
+        let any_ref: &dyn VtidAny = &stale;
+        assert!(any_ref.downcast_ref::<Foo>().is_none());
+    }
+
+    #[test]
+    fn downcast_ref_rejects_vtid_collision_with_different_concrete_type() {
+        // `Evil::vtid()` is identical to `Foo::vtid()` by construction. The `TypeId` check is
+        // what must reject this, since the `Vtid` check alone would accept it.
+        let evil = Evil(0xdead_beef);
+        let any_ref: &dyn VtidAny = &evil;
+        assert!(any_ref.downcast_ref::<Foo>().is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn downcast_matching_type_succeeds() {
+        let boxed: alloc::boxed::Box<dyn VtidAny> = alloc::boxed::Box::new(Foo(9));
+        let foo = boxed.downcast::<Foo>().ok().unwrap();
+        assert_eq!(foo.0, 9);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn downcast_vtid_collision_fails_and_returns_the_box() {
+        let boxed: alloc::boxed::Box<dyn VtidAny> = alloc::boxed::Box::new(Evil(0));
+        assert!(boxed.downcast::<Foo>().is_err());
+    }
+}
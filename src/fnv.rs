@@ -0,0 +1,32 @@
+//! A tiny fixed (non-randomized) FNV-1a hasher.
+//!
+//! [`core::hash::Hash`] alone does not guarantee a stable result: the default hasher used by
+//! `HashMap` (and the one picked implicitly by most `Hasher::finish()` call sites) seeds itself
+//! randomly per process. `Vtid`'s byte/string encoding needs the *same* input to always produce
+//! the *same* hash within a single compiled binary, so it uses this hasher explicitly instead.
+
+use core::hash::Hasher;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+pub(crate) struct FnvHasher(u64);
+
+impl FnvHasher {
+    pub(crate) fn new() -> Self {
+        FnvHasher(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
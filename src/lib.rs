@@ -8,6 +8,10 @@
 //! - **Derive Macro Support**: Easily derive the `HasVtid` trait for your types.
 //! - **`no_std` Compatible**: Use in embedded and other `no_std` environments.
 //! - **Minimal Dependencies**: Zero dependencies, except for the derive macro.
+//! - **Stable Encoding**: `Vtid` has a canonical byte/string form (`to_bytes`, `Display`,
+//!   `FromStr`) and an optional `serde` feature, so it can be logged or sent over a socket.
+//! - **Runtime Registry**: an optional `alloc`-gated `VtidRegistry` tracks which registered
+//!   types changed `Vtid` across a `dlopen`, for cdylib hot-reload hosts.
 //!
 //! ## 📦 Installation
 //!
@@ -48,7 +52,32 @@
 
 #![no_std]
 
-use core::{any::TypeId, fmt};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::{
+    any::TypeId,
+    fmt,
+    hash::{Hash, Hasher as _},
+    str::FromStr,
+};
+
+mod fnv;
+
+use fnv::FnvHasher;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+/// Hashes a `TypeId` through a fixed (non-randomized) hasher to get a concrete, stable `u64`.
+///
+/// This is what makes `Vtid` encodable: `TypeId` itself exposes no stable numeric
+/// representation, but hashing it deterministically within a single compiled binary gives one.
+fn hash_type_id(tid: TypeId) -> u64 {
+    let mut hasher = FnvHasher::new();
+    tid.hash(&mut hasher);
+    hasher.finish()
+}
 
 /// A trait that provides type identification that can change between crate compilations.
 ///
@@ -68,10 +97,13 @@ pub trait HasVtid {
 ///
 /// This allows reusing existing instances of a type from cdylib crate when new version is linked
 /// if Vtid does not change, since crate is not recompiled and thus memory layout of the type is unchanged.
+///
+/// `Vtid` has a canonical 128-bit encoding (see [`Vtid::to_bytes`]) so it can be logged,
+/// persisted, or sent between a host and a reloaded cdylib over a socket.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Vtid {
-    tid: TypeId,
-    base_id: u64,
+    pub(crate) base_id: u64,
+    pub(crate) tid_hash: u64,
 }
 
 impl Vtid {
@@ -85,15 +117,118 @@ impl Vtid {
     {
         T::vtid()
     }
+
+    /// Returns the canonical 128-bit encoding of this `Vtid`.
+    ///
+    /// The high 8 bytes are the `base_id` (big-endian), the low 8 bytes are the type's
+    /// `TypeId` hashed through a fixed hasher (big-endian).
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.base_id.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.tid_hash.to_be_bytes());
+        bytes
+    }
+
+    /// Reconstructs a `Vtid` from its canonical 128-bit encoding.
+    ///
+    /// See [`Vtid::to_bytes`] for the layout.
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        let mut base_id_bytes = [0u8; 8];
+        let mut tid_hash_bytes = [0u8; 8];
+        base_id_bytes.copy_from_slice(&bytes[..8]);
+        tid_hash_bytes.copy_from_slice(&bytes[8..]);
+
+        Vtid {
+            base_id: u64::from_be_bytes(base_id_bytes),
+            tid_hash: u64::from_be_bytes(tid_hash_bytes),
+        }
+    }
 }
 
 impl fmt::Debug for Vtid {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Vtid({:#016x}, {:?})", self.base_id, self.tid)
+        write!(f, "Vtid({:#016x}, {:#016x})", self.base_id, self.tid_hash)
+    }
+}
+
+impl fmt::Display for Vtid {
+    /// Formats the `Vtid` as two hyphenated, lowercase-hex 16-digit groups: `base_id-tid_hash`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}-{:016x}", self.base_id, self.tid_hash)
+    }
+}
+
+/// An error returned when parsing a [`Vtid`] from its [`Display`](fmt::Display) form fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseVtidError;
+
+impl fmt::Display for ParseVtidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Vtid string")
+    }
+}
+
+impl FromStr for Vtid {
+    type Err = ParseVtidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (base_id_str, tid_hash_str) = s.split_once('-').ok_or(ParseVtidError)?;
+
+        if base_id_str.len() != 16 || tid_hash_str.len() != 16 {
+            return Err(ParseVtidError);
+        }
+
+        let base_id = u64::from_str_radix(base_id_str, 16).map_err(|_| ParseVtidError)?;
+        let tid_hash = u64::from_str_radix(tid_hash_str, 16).map_err(|_| ParseVtidError)?;
+
+        Ok(Vtid { base_id, tid_hash })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+
+    fn sample() -> Vtid {
+        Vtid {
+            base_id: 0x0123_4567_89ab_cdef,
+            tid_hash: 0xfedc_ba98_7654_3210,
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let vtid = sample();
+        assert_eq!(Vtid::from_bytes(vtid.to_bytes()), vtid);
+    }
+
+    #[test]
+    fn string_round_trip() {
+        let vtid = sample();
+        let parsed: Vtid = vtid.to_string().parse().unwrap();
+        assert_eq!(parsed, vtid);
+    }
+
+    #[test]
+    fn from_str_rejects_malformed_input() {
+        assert!("not-a-vtid".parse::<Vtid>().is_err());
+        assert!("01234567-89abcdef".parse::<Vtid>().is_err());
     }
 }
 
+mod any;
+pub use any::VtidAny;
+
+#[cfg(feature = "alloc")]
+mod registry;
+#[cfg(feature = "alloc")]
+pub use registry::{Changed, Entry, VtidRegistry, VtidSnapshot};
+
 #[cfg(feature = "derive")]
 #[doc(hidden)]
 pub mod private {
@@ -105,8 +240,8 @@ pub mod private {
     /// * `T` - The type to create a Vtid for. Must implement 'static.
     pub fn vtid<T: 'static>(base_id: u64) -> super::Vtid {
         super::Vtid {
-            tid: TypeId::of::<T>(),
             base_id,
+            tid_hash: super::hash_type_id(TypeId::of::<T>()),
         }
     }
 }
@@ -0,0 +1,173 @@
+use alloc::collections::BTreeMap;
+use core::any::TypeId;
+
+use crate::{HasVtid, Vtid};
+
+/// An entry stored in a [`VtidRegistry`]: a type's `Vtid` at registration time paired with
+/// caller-supplied metadata (e.g. size, align, a drop glue function pointer, or any other
+/// payload the host needs to manage a cached instance of that type).
+pub struct Entry<M> {
+    pub vtid: Vtid,
+    pub meta: M,
+}
+
+/// A point-in-time capture of a [`VtidRegistry`]'s `Vtid`s, kept without the metadata so it is
+/// cheap to hold onto across a `dlopen` and compare against with [`VtidRegistry::diff`].
+#[derive(Clone)]
+pub struct VtidSnapshot {
+    vtids: BTreeMap<TypeId, Vtid>,
+}
+
+/// A registered type whose `Vtid` differs between a [`VtidSnapshot`] and the registry it is
+/// diffed against.
+pub struct Changed {
+    pub tid: TypeId,
+    pub previous: Vtid,
+    pub current: Vtid,
+}
+
+/// A registry mapping each registered type to its [`Vtid`] and caller-supplied metadata.
+///
+/// This is the integration point that makes the crate's hot-reload use case actionable: a
+/// host can [`snapshot`](VtidRegistry::snapshot) the registry before `dlopen`-ing a new module
+/// and later [`diff`](VtidRegistry::diff) a freshly (re)populated registry against that
+/// snapshot to learn which registered types changed - and therefore which cached instances
+/// must be dropped and rebuilt versus which can be reused in place.
+///
+/// Types are keyed by their Rust `TypeId`, so registering and looking up a type only makes
+/// sense against a `T` the host itself can still name - the registry does not attempt to
+/// match up types across a reload by name or layout.
+pub struct VtidRegistry<M> {
+    entries: BTreeMap<TypeId, Entry<M>>,
+}
+
+impl<M> VtidRegistry<M> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        VtidRegistry {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Registers (or re-registers) `T` with its current `Vtid` and the given metadata.
+    pub fn register<T: HasVtid + 'static>(&mut self, meta: M) {
+        self.entries.insert(
+            TypeId::of::<T>(),
+            Entry {
+                vtid: Vtid::of::<T>(),
+                meta,
+            },
+        );
+    }
+
+    /// Returns the registered entry for `T`, if any.
+    pub fn lookup<T: 'static>(&self) -> Option<&Entry<M>> {
+        self.entries.get(&TypeId::of::<T>())
+    }
+
+    /// Captures the current `Vtid` of every registered type.
+    pub fn snapshot(&self) -> VtidSnapshot {
+        VtidSnapshot {
+            vtids: self
+                .entries
+                .iter()
+                .map(|(tid, entry)| (*tid, entry.vtid))
+                .collect(),
+        }
+    }
+
+    /// Returns the registered types whose `Vtid` changed since `previous` was captured.
+    ///
+    /// A type present in `previous` but no longer registered (or vice versa) is not reported;
+    /// only types known to both sides are compared.
+    pub fn diff<'a>(&'a self, previous: &'a VtidSnapshot) -> impl Iterator<Item = Changed> + 'a {
+        self.entries.iter().filter_map(move |(tid, entry)| {
+            let previous_vtid = *previous.vtids.get(tid)?;
+            if previous_vtid == entry.vtid {
+                return None;
+            }
+
+            Some(Changed {
+                tid: *tid,
+                previous: previous_vtid,
+                current: entry.vtid,
+            })
+        })
+    }
+}
+
+impl<M> Default for VtidRegistry<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Foo;
+    impl HasVtid for Foo {
+        fn vtid() -> Vtid {
+            Vtid {
+                base_id: 1,
+                tid_hash: crate::hash_type_id(TypeId::of::<Foo>()),
+            }
+        }
+    }
+
+    struct Bar;
+    impl HasVtid for Bar {
+        fn vtid() -> Vtid {
+            Vtid {
+                base_id: 1,
+                tid_hash: crate::hash_type_id(TypeId::of::<Bar>()),
+            }
+        }
+    }
+
+    #[test]
+    fn register_then_lookup_returns_the_meta() {
+        let mut registry = VtidRegistry::new();
+        registry.register::<Foo>("foo-meta");
+
+        assert_eq!(registry.lookup::<Foo>().map(|entry| entry.meta), Some("foo-meta"));
+        assert!(registry.lookup::<Bar>().is_none());
+    }
+
+    #[test]
+    fn diff_is_empty_when_nothing_changed() {
+        let mut registry = VtidRegistry::new();
+        registry.register::<Foo>(());
+        registry.register::<Bar>(());
+
+        let snapshot = registry.snapshot();
+
+        assert_eq!(registry.diff(&snapshot).count(), 0);
+    }
+
+    #[test]
+    fn diff_reports_a_type_whose_vtid_changed() {
+        let mut registry = VtidRegistry::new();
+        registry.register::<Foo>(());
+        registry.register::<Bar>(());
+        let snapshot = registry.snapshot();
+
+        // Re-register Foo as if it came back with a new base_id after a reload.
+        registry.entries.insert(
+            TypeId::of::<Foo>(),
+            Entry {
+                vtid: Vtid {
+                    base_id: 2,
+                    tid_hash: crate::hash_type_id(TypeId::of::<Foo>()),
+                },
+                meta: (),
+            },
+        );
+
+        let changed: alloc::vec::Vec<Changed> = registry.diff(&snapshot).collect();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].tid, TypeId::of::<Foo>());
+        assert_eq!(changed[0].previous, snapshot.vtids[&TypeId::of::<Foo>()]);
+    }
+}
@@ -0,0 +1,338 @@
+use core::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Vtid;
+
+impl Serialize for Vtid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+}
+
+struct VtidStrVisitor;
+
+impl de::Visitor<'_> for VtidStrVisitor {
+    type Value = Vtid;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a hyphenated Vtid string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Vtid::from_str(v).map_err(de::Error::custom)
+    }
+}
+
+struct VtidBytesVisitor;
+
+impl de::Visitor<'_> for VtidBytesVisitor {
+    type Value = Vtid;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "16 bytes encoding a Vtid")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let bytes: [u8; 16] = v
+            .try_into()
+            .map_err(|_| de::Error::invalid_length(v.len(), &self))?;
+        Ok(Vtid::from_bytes(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for Vtid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(VtidStrVisitor)
+        } else {
+            // Paired with `serialize_bytes` above: both sides must agree on the wire shape,
+            // e.g. a length-prefixed byte buffer under bincode. Deserializing through the
+            // generic `[u8; 16]` impl instead would silently read the wrong bytes on formats
+            // that prefix `serialize_bytes` output with a length.
+            deserializer.deserialize_bytes(VtidBytesVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use serde::{
+        de::{value::Error as DeError, IntoDeserializer},
+        forward_to_deserialize_any,
+        ser::Impossible,
+    };
+    use std::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use super::*;
+
+    fn sample() -> Vtid {
+        Vtid {
+            base_id: 0x1111_2222_3333_4444,
+            tid_hash: 0x5555_6666_7777_8888,
+        }
+    }
+
+    /// A minimal `Serializer` that only supports the two primitives `Vtid` actually emits
+    /// (`serialize_str`, reached through `collect_str`, and `serialize_bytes`), so this crate
+    /// doesn't need a real data-format dev-dependency to test its `Serialize` impl.
+    struct RecordingSerializer {
+        human_readable: bool,
+    }
+
+    enum Recorded {
+        Str(String),
+        Bytes(Vec<u8>),
+    }
+
+    impl Serializer for RecordingSerializer {
+        type Ok = Recorded;
+        type Error = DeError;
+        type SerializeSeq = Impossible<Recorded, DeError>;
+        type SerializeTuple = Impossible<Recorded, DeError>;
+        type SerializeTupleStruct = Impossible<Recorded, DeError>;
+        type SerializeTupleVariant = Impossible<Recorded, DeError>;
+        type SerializeMap = Impossible<Recorded, DeError>;
+        type SerializeStruct = Impossible<Recorded, DeError>;
+        type SerializeStructVariant = Impossible<Recorded, DeError>;
+
+        fn is_human_readable(&self) -> bool {
+            self.human_readable
+        }
+
+        fn serialize_str(self, v: &str) -> Result<Recorded, DeError> {
+            Ok(Recorded::Str(v.into()))
+        }
+
+        fn serialize_bytes(self, v: &[u8]) -> Result<Recorded, DeError> {
+            Ok(Recorded::Bytes(v.into()))
+        }
+
+        serde::serde_if_integer128! {
+            fn serialize_i128(self, _: i128) -> Result<Recorded, DeError> {
+                Err(serde::ser::Error::custom("unexpected for Vtid"))
+            }
+            fn serialize_u128(self, _: u128) -> Result<Recorded, DeError> {
+                Err(serde::ser::Error::custom("unexpected for Vtid"))
+            }
+        }
+
+        fn serialize_bool(self, _: bool) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_i8(self, _: i8) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_i16(self, _: i16) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_i32(self, _: i32) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_i64(self, _: i64) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_u8(self, _: u8) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_u16(self, _: u16) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_u32(self, _: u32) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_u64(self, _: u64) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_f32(self, _: f32) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_f64(self, _: f64) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_char(self, _: char) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_none(self) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_unit(self) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_unit_struct(self, _: &'static str) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_unit_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+        ) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Recorded, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_tuple_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleStruct, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_tuple_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleVariant, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStruct, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+        fn serialize_struct_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStructVariant, DeError> {
+            Err(serde::ser::Error::custom("unexpected for Vtid"))
+        }
+    }
+
+    /// Forces `is_human_readable` on top of a `serde::de::value` deserializer, which always
+    /// reports `true` otherwise, so the non-human-readable (`deserialize_bytes`) path can
+    /// actually be exercised.
+    struct ForcedReadable<'de, D> {
+        inner: D,
+        human_readable: bool,
+        marker: core::marker::PhantomData<&'de ()>,
+    }
+
+    impl<'de, D: de::Deserializer<'de, Error = DeError>> de::Deserializer<'de>
+        for ForcedReadable<'de, D>
+    {
+        type Error = DeError;
+
+        fn is_human_readable(&self) -> bool {
+            self.human_readable
+        }
+
+        fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+            self.inner.deserialize_any(visitor)
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    #[test]
+    fn human_readable_serialize_emits_display_string() {
+        let vtid = sample();
+        match vtid
+            .serialize(RecordingSerializer {
+                human_readable: true,
+            })
+            .ok()
+            .unwrap()
+        {
+            Recorded::Str(s) => assert_eq!(s, vtid.to_string()),
+            Recorded::Bytes(_) => panic!("expected a human-readable string"),
+        }
+    }
+
+    #[test]
+    fn non_human_readable_serialize_emits_canonical_bytes() {
+        let vtid = sample();
+        match vtid
+            .serialize(RecordingSerializer {
+                human_readable: false,
+            })
+            .ok()
+            .unwrap()
+        {
+            Recorded::Bytes(bytes) => assert_eq!(bytes, vtid.to_bytes()),
+            Recorded::Str(_) => panic!("expected bytes"),
+        }
+    }
+
+    #[test]
+    fn human_readable_round_trip() {
+        let vtid = sample();
+        let s = vtid.to_string();
+        let deserializer = ForcedReadable {
+            inner: s.as_str().into_deserializer(),
+            human_readable: true,
+            marker: core::marker::PhantomData,
+        };
+        assert_eq!(Vtid::deserialize(deserializer).unwrap(), vtid);
+    }
+
+    #[test]
+    fn non_human_readable_round_trip() {
+        let vtid = sample();
+        let bytes = vtid.to_bytes();
+        let deserializer = ForcedReadable {
+            inner: (&bytes[..]).into_deserializer(),
+            human_readable: false,
+            marker: core::marker::PhantomData,
+        };
+        assert_eq!(Vtid::deserialize(deserializer).unwrap(), vtid);
+    }
+}